@@ -4,6 +4,91 @@ use rusty_engine::prelude::*;
 const PLAYER_SPEED: f32 = 250.0;
 const ROAD_SPEED: f32 = 400.0;
 
+/// How many seconds of survival it takes for the difficulty factor to grow by 1.0.
+const DIFFICULTY_RAMP_SECONDS: f32 = 30.0;
+
+/// Base number of seconds between newly-spawned obstacles at difficulty 1.0.
+const OBSTACLE_SPAWN_INTERVAL: f32 = 3.0;
+
+/// Number of obstacles present at game start.
+const INITIAL_OBSTACLE_COUNT: usize = 3;
+
+/// Upper bound on the total number of obstacles (including the initial ones) that the difficulty
+/// ramp may spawn up to, so a long run can't grow
+/// the sprite count (and per-frame `move_road_objects` work) without limit.
+const MAX_OBSTACLES: usize = 12;
+
+/// Passive health regeneration rate, in HP per second, applied on frames with no obstacle hit.
+const HEALTH_REGEN_PER_SECOND: f32 = 0.25;
+
+/// Starting/maximum health for the player's car.
+const STARTING_HEALTH: f32 = 5.0;
+
+/// Health restored by collecting a pickup.
+const PICKUP_HEAL_AMOUNT: f32 = 2.0;
+
+/// Speed the player starts (and restarts) at, as a multiplier of `ROAD_SPEED`.
+const CRUISE_SPEED: f32 = 1.0;
+
+/// Maximum speed reachable by throttling, as a multiplier of `ROAD_SPEED`.
+const MAX_SPEED: f32 = 1.6;
+
+/// How fast `speed` climbs toward `MAX_SPEED` per second while throttling.
+const ACCEL_RATE: f32 = 0.8;
+
+/// How fast `speed` falls toward `0.0` per second while braking.
+const BRAKE_RATE: f32 = 1.2;
+
+/// Minimum world-scroll speed (as a multiplier of `ROAD_SPEED`) regardless of how low `speed`
+/// coasts or brakes to. Without this, a fully stopped player stops the whole scene and becomes
+/// invulnerable; this keeps obstacles creeping in so idling still carries risk.
+const MIN_SCROLL_SPEED: f32 = 0.3;
+
+/// How fast `speed` decays toward `0.0` per second when neither throttling nor braking.
+const COAST_DECAY_RATE: f32 = 0.3;
+
+/// Speed below which the player is considered "stopped".
+const STOPPED_THRESHOLD: f32 = 0.05;
+
+/// File the all-time best score is persisted to between runs.
+const HIGH_SCORE_FILE: &str = "highscore.txt";
+
+/// Loads the all-time best score from `HIGH_SCORE_FILE`, defaulting to `0.0` if it doesn't exist
+/// or can't be parsed.
+fn load_high_score() -> f32 {
+    std::fs::read_to_string(HIGH_SCORE_FILE)
+        .ok()
+        .and_then(|contents| contents.trim().parse().ok())
+        .unwrap_or(0.0)
+}
+
+/// Writes `score` to `HIGH_SCORE_FILE`, silently giving up if the file can't be written.
+fn save_high_score(score: f32) {
+    let _ = std::fs::write(HIGH_SCORE_FILE, score.to_string());
+}
+
+/// Tracks a car's health as floating-point current/max values plus pending, unapplied damage.
+///
+/// Obstacle collisions accumulate into `damage` during `handle_collisions` rather than mutating
+/// `current` directly, so that several simultaneous collision events in one frame are resolved
+/// deterministically by a single `apply_damage` step.
+struct HitPoints {
+    current: f32,
+    max: f32,
+    damage: f32,
+}
+
+impl HitPoints {
+    /// Creates a full-health `HitPoints` with the given maximum.
+    fn new(max: f32) -> Self {
+        HitPoints {
+            current: max,
+            max,
+            damage: 0.0,
+        }
+    }
+}
+
 /// Represents the state of the game.
 ///
 /// This struct holds information about the player's name, health amount, and whether the game
@@ -11,8 +96,23 @@ const ROAD_SPEED: f32 = 400.0;
 #[derive(Resource)]
 struct GameState<'name> {
     player_name: &'name str,
-    health_amount: u8,
+    health: HitPoints,
     lost: bool,
+    /// Total seconds the player has survived so far.
+    elapsed: f32,
+    /// Scaling factor derived from `elapsed` that ramps the game's difficulty over time.
+    difficulty: f32,
+    /// Counts down to the next obstacle spawn; reset every time it fires.
+    obstacle_timer: f32,
+    /// Number of obstacles spawned so far, used to label newly-added obstacle sprites.
+    obstacle_count: usize,
+    /// Distance traveled this run, used as the player's score.
+    distance: f32,
+    /// All-time best score, loaded from and persisted to `HIGH_SCORE_FILE`.
+    best_score: f32,
+    /// Player's current forward speed, as a multiplier of `ROAD_SPEED`; drives how fast the
+    /// whole scene scrolls.
+    speed: f32,
 }
 
 // ----------------------------------------------------------------------------------------------
@@ -132,6 +232,37 @@ fn add_obstacles(game: &mut Game<GameState>) {
     }
 }
 
+// ----------------------------------------------------------------------------------------------
+/// Adds health pickups to the game.
+///
+/// This function initializes and adds pickup sprites to the game instance, using a preset distinct
+/// from the obstacle presets. Pickups scroll and recycle exactly like obstacles in
+/// `move_road_objects`, but restore health instead of damaging the player on collision.
+///
+/// # Arguments
+///
+/// * `game` - A mutable reference to the game instance where the pickups will be added.
+///
+/// # Example
+///
+/// ```
+/// use my_game_library::{Game, GameState};
+///
+/// let mut game = Game::<GameState>::new();
+/// add_pickups(&mut game);
+/// ```
+fn add_pickups(game: &mut Game<GameState>) {
+    const NUM_PICKUPS: u8 = 2;
+
+    for i in 0..NUM_PICKUPS {
+        let pickup = game.add_sprite(format!("pickup{}", i), SpritePreset::RollingBallBlue);
+        pickup.layer = 5.0;
+        pickup.collision = true;
+        pickup.translation.x = thread_rng().gen_range(800.0..1600.0);
+        pickup.translation.y = thread_rng().gen_range(-300.0..300.0);
+    }
+}
+
 // ----------------------------------------------------------------------------------------------
 /// Creates a message in the game with the specified label, text, and position.
 ///
@@ -169,8 +300,15 @@ fn main() {
     const PLAYER_NAME: &str = "Colin";
     let initial_game_state = GameState {
         player_name: PLAYER_NAME,
-        health_amount: 5,
+        health: HitPoints::new(STARTING_HEALTH),
         lost: false,
+        elapsed: 0.0,
+        difficulty: 1.0,
+        obstacle_timer: OBSTACLE_SPAWN_INTERVAL,
+        obstacle_count: INITIAL_OBSTACLE_COUNT,
+        distance: 0.0,
+        best_score: load_high_score(),
+        speed: CRUISE_SPEED,
     };
 
     // Create the player sprite
@@ -188,15 +326,27 @@ fn main() {
     // Road obstacles
     add_obstacles(&mut game);
 
+    // Health pickups
+    add_pickups(&mut game);
+
     // Health info on top right
     create_message(
         &mut game,
         "health_message",
-        &format!("Health: {}", initial_game_state.health_amount),
+        &format!(
+            "Health: {:.0}/{:.0}",
+            initial_game_state.health.current, initial_game_state.health.max
+        ),
         550.0,
         320.0,
     );
 
+    // Live score, shown top center
+    create_message(&mut game, "score_message", "Score: 0", 0.0, 320.0);
+
+    // "Stopped" indicator, shown bottom center, blank unless the player has coasted to a halt
+    create_message(&mut game, "speed_message", "", 0.0, -320.0);
+
     // Add one or more functions with logic for the game. When the game is run, the logic
     // functions will run in the order they were added.
     game.add_logic(game_logic);
@@ -205,13 +355,41 @@ fn main() {
     game.run(initial_game_state);
 }
 
+// ----------------------------------------------------------------------------------------------
+/// Spawns a single additional obstacle at a random position off the right edge of the screen.
+///
+/// This function is called from `game_logic` whenever the obstacle spawn timer fires, growing
+/// the total number of obstacles on the road as the player survives longer.
+///
+/// # Arguments
+///
+/// * `engine` - A mutable reference to the game engine.
+/// * `game_state` - A mutable reference to the current game state.
+fn spawn_obstacle(engine: &mut Engine, game_state: &mut GameState) {
+    let obstacle_presets = [
+        SpritePreset::RacingBarrelBlue,
+        SpritePreset::RacingBarrelRed,
+        SpritePreset::RacingConeStraight,
+    ];
+    let preset = *obstacle_presets.choose(&mut thread_rng()).unwrap();
+
+    let obstacle = engine.add_sprite(format!("obstacle{}", game_state.obstacle_count), preset);
+    obstacle.layer = 5.0;
+    obstacle.collision = true;
+    obstacle.translation.x = thread_rng().gen_range(800.0..1600.0);
+    obstacle.translation.y = thread_rng().gen_range(-300.0..300.0);
+
+    game_state.obstacle_count += 1;
+}
+
 // ----------------------------------------------------------------------------------------------
 // Game Logic Helpers
 // ----------------------------------------------------------------------------------------------
-/// Handles keyboard input for player movement and game control.
+/// Handles keyboard input for player movement, throttle/brake, and game control.
 ///
-/// This function updates the player's position and rotation based on keyboard input,
-/// as well as checks for out-of-bounds conditions to end the game.
+/// This function updates the player's lane position and rotation from Up/Down, updates
+/// `game_state.speed` from Right (throttle) and Left (brake), and checks for out-of-bounds
+/// conditions to end the game.
 ///
 /// # Arguments
 ///
@@ -249,8 +427,25 @@ fn handle_keyboard(engine: &mut Engine, game_state: &mut GameState) {
 
     // End the game when OOB
     if player.translation.y < -360.0 || player.translation.y > 360.0 {
-        game_state.health_amount = 0;
+        game_state.health.current = 0.0;
     }
+
+    // Throttle/brake: Right accelerates toward MAX_SPEED, Left brakes toward a stop, and
+    // releasing both coasts back down toward a stop too.
+    if engine.keyboard_state.pressed(KeyCode::Right) {
+        game_state.speed = (game_state.speed + ACCEL_RATE * engine.delta_f32).min(MAX_SPEED);
+    } else if engine.keyboard_state.pressed(KeyCode::Left) {
+        game_state.speed = (game_state.speed - BRAKE_RATE * engine.delta_f32).max(0.0);
+    } else {
+        game_state.speed = (game_state.speed - COAST_DECAY_RATE * engine.delta_f32).max(0.0);
+    }
+
+    let speed_message = engine.texts.get_mut("speed_message").unwrap();
+    speed_message.value = if game_state.speed < STOPPED_THRESHOLD {
+        "Stopped!".to_string()
+    } else {
+        String::new()
+    };
 }
 
 // ----------------------------------------------------------------------------------------------
@@ -258,11 +453,17 @@ fn handle_keyboard(engine: &mut Engine, game_state: &mut GameState) {
 ///
 /// This function updates the position of road lines and obstacles by moving them to the left,
 /// simulating the effect of the player's movement. If road objects move out of the screen,
-/// they are repositioned to the other side to create an endless scrolling effect.
+/// they are repositioned to the other side to create an endless scrolling effect. The `difficulty`
+/// factor raises the effective road speed and tightens the respawn range so obstacles recycle
+/// closer together the longer the player survives, while `speed` scales the scroll with how hard
+/// the player is throttling.
 ///
 /// # Arguments
 ///
 /// * `engine` - A mutable reference to the game engine.
+/// * `difficulty` - Current difficulty scaling factor (1.0 at game start, growing over time).
+/// * `speed` - Player's current throttle speed, as a multiplier of `ROAD_SPEED`. The scroll uses
+///   at least `MIN_SCROLL_SPEED`, so a fully stopped player still has obstacles creeping in.
 ///
 /// # Example
 ///
@@ -271,14 +472,20 @@ fn handle_keyboard(engine: &mut Engine, game_state: &mut GameState) {
 ///
 /// fn main() {
 ///     let mut engine = Engine::new();
-///     move_road_objects(&mut engine);
+///     move_road_objects(&mut engine, 1.0, 1.0);
 /// }
 /// ```
-fn move_road_objects(engine: &mut Engine) {
+fn move_road_objects(engine: &mut Engine, difficulty: f32, speed: f32) {
+    let road_speed = ROAD_SPEED * difficulty * speed.max(MIN_SCROLL_SPEED);
+
+    // Obstacles respawn closer to the player as the difficulty climbs.
+    let respawn_min = 800.0;
+    let respawn_max = (1600.0 / difficulty).max(respawn_min + 100.0);
+
     for sprite in engine.sprites.values_mut() {
         // Road lines
         if sprite.label.starts_with("roadline") {
-            sprite.translation.x -= ROAD_SPEED * engine.delta_f32;
+            sprite.translation.x -= road_speed * engine.delta_f32;
 
             // Translate road objects to other side of screen if gone too far
             if sprite.translation.x < -675.0 {
@@ -286,11 +493,11 @@ fn move_road_objects(engine: &mut Engine) {
             }
         }
 
-        // Obstacles
-        if sprite.label.starts_with("obstacle") {
-            sprite.translation.x -= ROAD_SPEED * engine.delta_f32;
+        // Obstacles and pickups scroll and recycle identically
+        if sprite.label.starts_with("obstacle") || sprite.label.starts_with("pickup") {
+            sprite.translation.x -= road_speed * engine.delta_f32;
             if sprite.translation.x < -800.0 {
-                sprite.translation.x = thread_rng().gen_range(800.0..1600.0);
+                sprite.translation.x = thread_rng().gen_range(respawn_min..respawn_max);
                 sprite.translation.y = thread_rng().gen_range(-300.0..300.0);
             }
         }
@@ -301,8 +508,10 @@ fn move_road_objects(engine: &mut Engine) {
 /// Handles collision events between game objects.
 ///
 /// This function processes collision events between game objects, specifically between the player
-/// and obstacles. It reduces the player's health upon collision, updates the health message, and
-/// plays a sound effect.
+/// and obstacles or pickups. Obstacle hits accumulate into `game_state.health.damage`, which
+/// `apply_damage` resolves once per frame; this keeps several simultaneous collision events in a
+/// single frame deterministic. Pickup hits instead restore health immediately, play a positive
+/// sound effect, and teleport the pickup off-screen so it can't be re-collected in the same pass.
 ///
 /// # Arguments
 ///
@@ -322,27 +531,69 @@ fn move_road_objects(engine: &mut Engine) {
 /// }
 /// ```
 fn handle_collisions(engine: &mut Engine, game_state: &mut GameState) {
-    let health_message = engine.texts.get_mut("health_message").unwrap();
-
     // Go through all collision events and act accordingly
     for event in engine.collision_events.drain(..) {
         // We don't care if obstacles collide with each other or collisions end
         if !event.pair.either_contains(game_state.player_name) || event.state.is_end() {
             continue;
         }
-        if game_state.health_amount > 0 {
-            game_state.health_amount -= 1;
-            health_message.value = format!("Health: {}", game_state.health_amount);
+
+        let pickup_label = [&event.pair.0, &event.pair.1]
+            .into_iter()
+            .find(|label| label.starts_with("pickup"))
+            .cloned();
+
+        if let Some(pickup_label) = pickup_label {
+            game_state.health.current = (game_state.health.current + PICKUP_HEAL_AMOUNT).min(game_state.health.max);
+            engine.audio_manager.play_sfx(SfxPreset::Confirmation1, 0.6);
+
+            // Teleport the pickup off-screen so it can't be re-collected this pass; it will
+            // recycle back into play the next time `move_road_objects` wraps it around.
+            if let Some(pickup) = engine.sprites.get_mut(&pickup_label) {
+                pickup.translation.x = -800.0;
+            }
+        } else {
+            game_state.health.damage += 1.0;
             engine.audio_manager.play_sfx(SfxPreset::Impact3, 0.7);
         }
     }
 }
 
+// ----------------------------------------------------------------------------------------------
+/// Applies any damage accumulated this frame and regenerates health otherwise.
+///
+/// This is where obstacle damage reaches `game_state.health.current`: it subtracts and zeroes out
+/// `damage` if any obstacle hits were detected this frame, or else (while health is above zero)
+/// restores a small amount of health scaled by `engine.delta_f32`, capped at `max`. Pickups,
+/// going out of bounds and `reset_game` change `current` elsewhere. The on-screen health message
+/// is updated to match.
+///
+/// # Arguments
+///
+/// * `engine` - A mutable reference to the game engine.
+/// * `game_state` - A mutable reference to the current game state.
+fn apply_damage(engine: &mut Engine, game_state: &mut GameState) {
+    let health = &mut game_state.health;
+
+    if health.damage > 0.0 {
+        health.current = (health.current - health.damage).max(0.0);
+        health.damage = 0.0;
+    } else if health.current > 0.0 {
+        // Only regen while still alive; a zeroed-out health (e.g. from going OOB) must stay
+        // zero until `check_health` ends the game, rather than ticking back up first.
+        health.current = (health.current + HEALTH_REGEN_PER_SECOND * engine.delta_f32).min(health.max);
+    }
+
+    let health_message = engine.texts.get_mut("health_message").unwrap();
+    health_message.value = format!("Health: {:.0}/{:.0}", health.current, health.max);
+}
+
 // ----------------------------------------------------------------------------------------------
 /// Checks the player's health status and handles game over conditions.
 ///
 /// This function checks the player's health amount, and if it reaches zero, it sets the game
-/// state to lost, displays a "Game Over" message, stops the game music, and plays a game over sound effect.
+/// state to lost, persists a new best score if one was set, displays a "Game Over" message with
+/// the final and all-time best score, stops the game music, and plays a game over sound effect.
 ///
 /// # Arguments
 ///
@@ -362,15 +613,92 @@ fn handle_collisions(engine: &mut Engine, game_state: &mut GameState) {
 /// }
 /// ```
 fn check_health(engine: &mut Engine, game_state: &mut GameState) {
-    if game_state.health_amount == 0 {
+    if game_state.health.current <= 0.0 {
         game_state.lost = true;
-        let game_over = engine.add_text("game over", "Game Over");
-        game_over.font_size = 128.0;
+
+        if game_state.distance > game_state.best_score {
+            game_state.best_score = game_state.distance;
+            save_high_score(game_state.best_score);
+        }
+
+        let game_over = engine.add_text(
+            "game over",
+            format!(
+                "Game Over\nScore: {:.0}\nBest: {:.0}",
+                game_state.distance, game_state.best_score
+            ),
+        );
+        game_over.font_size = 96.0;
         engine.audio_manager.stop_music();
         engine.audio_manager.play_sfx(SfxPreset::Jingle3, 0.75);
     }
 }
 
+// ----------------------------------------------------------------------------------------------
+/// Tears down the "Game Over" screen and re-seeds the game so play can resume without relaunching.
+///
+/// This restores `game_state.health` to full, removes the "game over" text, resets the player's
+/// position and rotation, scatters obstacles back to randomized off-screen starting positions, and
+/// restarts the background music.
+///
+/// # Arguments
+///
+/// * `engine` - A mutable reference to the game engine.
+/// * `game_state` - A mutable reference to the current game state.
+fn reset_game(engine: &mut Engine, game_state: &mut GameState) {
+    // Restore health, the difficulty ramp and the score
+    game_state.health = HitPoints::new(STARTING_HEALTH);
+    game_state.elapsed = 0.0;
+    game_state.difficulty = 1.0;
+    game_state.obstacle_timer = OBSTACLE_SPAWN_INTERVAL;
+    game_state.distance = 0.0;
+    engine.texts.get_mut("score_message").unwrap().value = "Score: 0".to_string();
+    game_state.speed = CRUISE_SPEED;
+    engine.texts.get_mut("speed_message").unwrap().value = String::new();
+
+    // Clear the "Game Over" text
+    engine.texts.remove("game over");
+
+    // Reset the player back to its starting position
+    let player = engine.sprites.get_mut(game_state.player_name).unwrap();
+    player.translation.x = -500.0;
+    player.translation.y = 0.0;
+    player.rotation = 0.0;
+
+    // Despawn any obstacles the difficulty ramp spawned during the run, so a restart goes back
+    // to the initial obstacle count instead of compounding it
+    for i in INITIAL_OBSTACLE_COUNT..game_state.obstacle_count {
+        engine.sprites.remove(&format!("obstacle{}", i));
+    }
+    game_state.obstacle_count = INITIAL_OBSTACLE_COUNT;
+
+    // Scatter obstacles and pickups back to randomized off-screen starting positions
+    for sprite in engine.sprites.values_mut() {
+        if sprite.label.starts_with("obstacle") || sprite.label.starts_with("pickup") {
+            sprite.translation.x = thread_rng().gen_range(800.0..1600.0);
+            sprite.translation.y = thread_rng().gen_range(-300.0..300.0);
+        }
+    }
+
+    // Restart the music
+    engine.audio_manager.play_music(MusicPreset::WhimsicalPopsicle, 0.2);
+
+    game_state.lost = false;
+}
+
+// ----------------------------------------------------------------------------------------------
+/// Listens for the restart key while the game is over and restarts the game when it's pressed.
+///
+/// # Arguments
+///
+/// * `engine` - A mutable reference to the game engine.
+/// * `game_state` - A mutable reference to the current game state.
+fn handle_restart(engine: &mut Engine, game_state: &mut GameState) {
+    if engine.keyboard_state.pressed(KeyCode::Space) || engine.keyboard_state.pressed(KeyCode::Return) {
+        reset_game(engine, game_state);
+    }
+}
+
 // ----------------------------------------------------------------------------------------------
 /// Handles the game logic, including player input, object movement, collisions, and game over conditions.
 ///
@@ -398,20 +726,45 @@ fn check_health(engine: &mut Engine, game_state: &mut GameState) {
 /// }
 /// ```
 fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
-    // Don't run any more game logic if the game has ended
+    // Once the game has ended, only listen for the restart key instead of running normal logic
     if game_state.lost {
+        handle_restart(engine, game_state);
         return;
     }
 
+    // Update the difficulty ramp based on how long the player has survived
+    game_state.elapsed += engine.delta_f32;
+    game_state.difficulty = 1.0 + game_state.elapsed / DIFFICULTY_RAMP_SECONDS;
+
+    // Update timer for difficulty: spawn a new obstacle whenever it elapses, up to MAX_OBSTACLES
+    game_state.obstacle_timer -= engine.delta_f32;
+    if game_state.obstacle_timer <= 0.0 {
+        if game_state.obstacle_count < MAX_OBSTACLES {
+            spawn_obstacle(engine, game_state);
+        }
+        game_state.obstacle_timer = (OBSTACLE_SPAWN_INTERVAL / game_state.difficulty).max(0.5);
+    }
+
     // Check for KB input
     handle_keyboard(engine, game_state);
 
+    // Accumulate score as distance traveled, and reflect it on screen. Matches the scroll floor
+    // in move_road_objects so the score keeps climbing (and the scene keeps scrolling) even while
+    // the player idles.
+    game_state.distance +=
+        ROAD_SPEED * game_state.difficulty * game_state.speed.max(MIN_SCROLL_SPEED) * engine.delta_f32;
+    let score_message = engine.texts.get_mut("score_message").unwrap();
+    score_message.value = format!("Score: {:.0}", game_state.distance);
+
     // Move road objects
-    move_road_objects(engine);
+    move_road_objects(engine, game_state.difficulty, game_state.speed);
 
     // Deal with collisions
     handle_collisions(engine, game_state);
 
+    // Apply any damage accumulated this frame (or regenerate health if there was none)
+    apply_damage(engine, game_state);
+
     // End the game if out of car health
     check_health(engine, game_state);
 }